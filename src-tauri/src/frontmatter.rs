@@ -0,0 +1,14 @@
+//! Shared parsing of Strata's leading `---`-delimited YAML frontmatter block,
+//! used by both the doc-type sniff in `lib.rs` and the search tokenizer.
+
+/// Byte range of the frontmatter body — between the opening and closing
+/// `---` delimiters, exclusive of both — or `None` if `content` doesn't open
+/// with a frontmatter block at all.
+pub fn span(content: &str) -> Option<(usize, usize)> {
+    if !content.starts_with("---\n") && !content.starts_with("---\r\n") {
+        return None;
+    }
+    let after_first = if content.starts_with("---\r\n") { 5 } else { 4 };
+    let end = content[after_first..].find("\n---")?;
+    Some((after_first, after_first + end))
+}
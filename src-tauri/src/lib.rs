@@ -1,20 +1,54 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::Serialize;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
 use tauri::menu::{MenuBuilder, MenuItem, SubmenuBuilder};
 
+mod frontmatter;
+mod search;
+use search::{SearchHit, SearchIndex};
+
+/// Default quiet window a path must sit in the debounce buffer before it's
+/// flushed, used when `start_watching` isn't given an explicit one.
+const DEFAULT_DEBOUNCE_WINDOW_MS: u64 = 75;
+/// How often the debounce thread wakes up to check for expired entries. Not
+/// itself configurable — it just needs to be finer-grained than the shortest
+/// debounce window callers are expected to pass.
+const DEBOUNCE_TICK: Duration = Duration::from_millis(25);
+
 // ── App state for file watcher ──
 
 struct AppState {
     watcher: Mutex<Option<RecommendedWatcher>>,
     write_guard: Arc<Mutex<HashSet<PathBuf>>>,
+    workspace_root: Mutex<Option<PathBuf>>,
+    ignore: Arc<Mutex<Gitignore>>,
+    debounce_stop: Mutex<Option<Arc<AtomicBool>>>,
+    search_index: Arc<Mutex<SearchIndex>>,
+    trash_guard: Arc<Mutex<HashSet<PathBuf>>>,
+    next_window_id: std::sync::atomic::AtomicU32,
+    /// Open-file targets waiting for their window's frontend to mount and
+    /// call `take_pending_open_file`, keyed by window label.
+    pending_open_files: Mutex<HashMap<String, OpenFilePayload>>,
+}
+
+/// The coalesced fate of a path once its debounce window expires.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PendingKind {
+    Created,
+    Modified,
+    Removed,
 }
 
+type PendingBuffer = Mutex<HashMap<PathBuf, (PendingKind, Instant)>>;
+
 #[derive(Clone, Serialize)]
 struct FsEvent {
     #[serde(rename = "relPath")]
@@ -29,19 +63,13 @@ fn is_strata_file(path: &std::path::Path) -> bool {
         Ok(c) => c,
         Err(_) => return false,
     };
-    if !content.starts_with("---\n") && !content.starts_with("---\r\n") {
+    let Some((start, end)) = frontmatter::span(&content) else {
         return false;
-    }
-    let after_first = if content.starts_with("---\r\n") { 5 } else { 4 };
-    if let Some(end) = content[after_first..].find("\n---") {
-        let frontmatter = &content[after_first..after_first + end];
-        frontmatter.lines().any(|line| {
-            let trimmed = line.trim();
-            trimmed == "doc-type: strata" || trimmed == "doc-type: \"strata\""
-        })
-    } else {
-        false
-    }
+    };
+    content[start..end].lines().any(|line| {
+        let trimmed = line.trim();
+        trimmed == "doc-type: strata" || trimmed == "doc-type: \"strata\""
+    })
 }
 
 /// Directories to skip during recursive file discovery
@@ -49,18 +77,163 @@ fn should_skip_dir(name: &str) -> bool {
     name.starts_with('.') || name == "node_modules" || name == "target" || name == "__pycache__"
 }
 
+/// Build a combined ignore matcher from every `.gitignore` and `.strataignore`
+/// found at the workspace root and in its (non-skipped) subdirectories.
+fn build_ignore_matcher(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    add_ignore_files(root, &mut builder);
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+fn add_ignore_files(dir: &Path, builder: &mut GitignoreBuilder) {
+    let _ = builder.add(dir.join(".gitignore"));
+    let _ = builder.add(dir.join(".strataignore"));
+
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if should_skip_dir(&name) {
+            continue;
+        }
+        add_ignore_files(&path, builder);
+    }
+}
+
+/// Collapse `.` and `..` segments and duplicate separators without touching disk.
+fn dedot(path: &std::path::Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Record `workspace` as the sandbox root that every filesystem command is
+/// confined to, canonicalizing it so later descendant checks are reliable.
+fn set_workspace_root(workspace: &str, state: &tauri::State<'_, AppState>) -> Result<PathBuf, String> {
+    let canonical = PathBuf::from(workspace).canonicalize().map_err(|e| e.to_string())?;
+
+    let mut root = state.workspace_root.lock().map_err(|e| e.to_string())?;
+    let changed = root.as_ref() != Some(&canonical);
+    *root = Some(canonical.clone());
+    drop(root);
+
+    if changed {
+        let mut index = state.search_index.lock().map_err(|e| e.to_string())?;
+        index.clear();
+    }
+
+    Ok(canonical)
+}
+
+/// Resolve a path sent from the frontend against the open workspace, rejecting
+/// anything that normalizes to a location outside it.
+fn resolve_in_workspace(raw: &str, state: &tauri::State<'_, AppState>) -> Result<PathBuf, String> {
+    let root = state.workspace_root.lock().map_err(|e| e.to_string())?.clone();
+    resolve_against_root(root.as_deref(), raw)
+}
+
+/// The actual sandboxing logic behind [`resolve_in_workspace`], pulled out of
+/// the `tauri::State` plumbing so it can be exercised directly in tests.
+fn resolve_against_root(root: Option<&Path>, raw: &str) -> Result<PathBuf, String> {
+    let root = root.ok_or_else(|| "no workspace is open".to_string())?;
+
+    let candidate = PathBuf::from(raw);
+    let joined = if candidate.is_absolute() {
+        candidate
+    } else {
+        root.join(candidate)
+    };
+
+    let resolved = dedot(&joined);
+    if !resolved.starts_with(root) {
+        return Err("path escapes the workspace".to_string());
+    }
+
+    reject_symlink_escape(root, &resolved)?;
+
+    Ok(resolved)
+}
+
+/// Re-check containment once symlinks are followed, which `dedot`'s purely
+/// textual `starts_with` comparison never does. Otherwise a symlink planted
+/// *inside* the workspace (e.g. by a malicious repo someone opens) whose
+/// target lives outside it — `notes/escape.md -> /etc/passwd` — would pass
+/// `resolve_against_root` cleanly and then get read or overwritten by
+/// `fs::read_to_string`/`fs::write`, which both follow links.
+///
+/// `resolved` may not exist yet (`write_file` creating a new file), so walk
+/// up to the nearest ancestor that does exist, canonicalize *that*, and
+/// rejoin the non-existent tail before re-checking containment.
+fn reject_symlink_escape(root: &Path, resolved: &Path) -> Result<(), String> {
+    let mut ancestor = resolved;
+    let mut tail = PathBuf::new();
+
+    while ancestor.symlink_metadata().is_err() {
+        let Some(name) = ancestor.file_name() else {
+            // Walked all the way up without finding anything that exists;
+            // nothing left to canonicalize against.
+            return Ok(());
+        };
+        tail = Path::new(name).join(&tail);
+        ancestor = match ancestor.parent() {
+            Some(parent) => parent,
+            None => return Ok(()),
+        };
+    }
+
+    let canonical = ancestor.canonicalize().map_err(|e| e.to_string())?.join(&tail);
+    if !canonical.starts_with(root) {
+        return Err("path escapes the workspace".to_string());
+    }
+    Ok(())
+}
+
+/// Normalize an already-sandboxed absolute path back to the workspace-relative,
+/// forward-slashed form every `FsEvent` uses, the same way the watcher does.
+fn workspace_relative(resolved: &Path, state: &tauri::State<'_, AppState>) -> Result<String, String> {
+    let root = state
+        .workspace_root
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| "no workspace is open".to_string())?;
+
+    Ok(resolved
+        .strip_prefix(&root)
+        .map(|r| r.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| resolved.to_string_lossy().replace('\\', "/")))
+}
+
 // ── File commands ──
 
-fn walk_dir(base: &PathBuf, dir: &PathBuf, files: &mut Vec<String>) -> Result<(), String> {
+fn walk_dir(base: &PathBuf, dir: &PathBuf, matcher: &Gitignore, files: &mut Vec<String>) -> Result<(), String> {
     let entries = fs::read_dir(dir).map_err(|e| e.to_string())?;
     for entry in entries {
         let entry = entry.map_err(|e| e.to_string())?;
         let path = entry.path();
         let name = entry.file_name().to_string_lossy().to_string();
 
+        if matcher.matched(&path, path.is_dir()).is_ignore() {
+            continue;
+        }
+
         if path.is_dir() {
             if !should_skip_dir(&name) {
-                walk_dir(base, &path, files)?;
+                walk_dir(base, &path, matcher, files)?;
             }
         } else if name.ends_with(".md") && !name.starts_with('.') && is_strata_file(&path) {
             if let Ok(rel) = path.strip_prefix(base) {
@@ -73,32 +246,96 @@ fn walk_dir(base: &PathBuf, dir: &PathBuf, files: &mut Vec<String>) -> Result<()
 }
 
 #[tauri::command]
-fn list_workspace_files(workspace: String) -> Result<Vec<String>, String> {
-    let base = PathBuf::from(&workspace);
+fn list_workspace_files(workspace: String, state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    let base = set_workspace_root(&workspace, &state)?;
+    let matcher = build_ignore_matcher(&base);
     let mut files = Vec::new();
-    walk_dir(&base, &base, &mut files)?;
+    walk_dir(&base, &base, &matcher, &mut files)?;
     files.sort();
+
+    let mut guard = state.ignore.lock().map_err(|e| e.to_string())?;
+    *guard = matcher;
+
+    let mut index = state.search_index.lock().map_err(|e| e.to_string())?;
+    for rel in &files {
+        index.index_file(&base, Path::new(rel));
+    }
+
     Ok(files)
 }
 
 #[tauri::command]
-fn read_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| e.to_string())
+fn read_file(path: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let resolved = resolve_in_workspace(&path, &state)?;
+    fs::read_to_string(&resolved).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn write_file(path: String, content: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let resolved = resolve_in_workspace(&path, &state)?;
+    fs::write(&resolved, &content).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn delete_file(
+    path: String,
+    permanent: bool,
+    app_handle: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let resolved = resolve_in_workspace(&path, &state)?;
+
+    if permanent {
+        return fs::remove_file(&resolved).map_err(|e| e.to_string());
+    }
+
+    trash::delete(&resolved).map_err(|e| e.to_string())?;
+
+    {
+        let mut guard = state.trash_guard.lock().map_err(|e| e.to_string())?;
+        guard.insert(resolved.clone());
+    }
+
+    let rel_path = workspace_relative(&resolved, &state)?;
+    let _ = app_handle.emit("fs:trashed", &FsEvent { rel_path });
+    Ok(())
 }
 
+/// Move a previously trashed file back to its original workspace location.
+///
+/// `trash::os_limited` (listing and restoring a specific trash entry) isn't
+/// implemented for macOS in the `trash` crate, so that target gets a clear
+/// "unsupported" error instead of an opaque propagated failure.
+#[cfg(not(target_os = "macos"))]
 #[tauri::command]
-fn write_file(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, &content).map_err(|e| e.to_string())
+fn restore_file(path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let resolved = resolve_in_workspace(&path, &state)?;
+
+    let items = trash::os_limited::list().map_err(|e| e.to_string())?;
+    let item = items
+        .into_iter()
+        .find(|item| PathBuf::from(item.original_path()) == resolved)
+        .ok_or_else(|| "file not found in trash".to_string())?;
+
+    trash::os_limited::restore_all(vec![item]).map_err(|e| e.to_string())?;
+
+    let mut guard = state.trash_guard.lock().map_err(|e| e.to_string())?;
+    guard.remove(&resolved);
+
+    Ok(())
 }
 
+#[cfg(target_os = "macos")]
 #[tauri::command]
-fn delete_file(path: String) -> Result<(), String> {
-    fs::remove_file(&path).map_err(|e| e.to_string())
+fn restore_file(_path: String, _state: tauri::State<'_, AppState>) -> Result<(), String> {
+    Err("restoring from trash isn't supported on macOS yet".to_string())
 }
 
 #[tauri::command]
-fn rename_file(old_path: String, new_path: String) -> Result<(), String> {
-    fs::rename(&old_path, &new_path).map_err(|e| e.to_string())
+fn rename_file(old_path: String, new_path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let resolved_old = resolve_in_workspace(&old_path, &state)?;
+    let resolved_new = resolve_in_workspace(&new_path, &state)?;
+    fs::rename(&resolved_old, &resolved_new).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -125,10 +362,160 @@ fn find_git_root() -> String {
     String::new()
 }
 
+// ── CLI launch / single-instance handling ──
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenFilePayload {
+    workspace: String,
+    rel_path: String,
+    line: Option<u32>,
+}
+
+/// Parse a launch's argv (including the binary name at index 0) into an
+/// optional `path[:line]` target and whether `--new-window` was requested.
+fn parse_launch_args(argv: &[String]) -> (Option<String>, Option<u32>, bool) {
+    let mut new_window = false;
+    let mut file_arg = None;
+
+    for arg in argv.iter().skip(1) {
+        if arg == "--new-window" {
+            new_window = true;
+        } else if file_arg.is_none() {
+            file_arg = Some(arg.clone());
+        }
+    }
+
+    match file_arg {
+        Some(raw) => match raw.rsplit_once(':').and_then(|(p, l)| l.parse::<u32>().ok().map(|l| (p, l))) {
+            Some((p, line)) => (Some(p.to_string()), Some(line), new_window),
+            None => (Some(raw), None, new_window),
+        },
+        None => (None, None, new_window),
+    }
+}
+
+/// Walk up from `path`'s directory to find the nearest `.git` folder, the
+/// same way `find_git_root` does for the current working directory.
+fn find_containing_workspace(path: &std::path::Path) -> PathBuf {
+    let start = path.parent().unwrap_or(path).to_path_buf();
+    let mut dir = start.as_path();
+    loop {
+        if dir.join(".git").exists() {
+            return dir.to_path_buf();
+        }
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => return start,
+        }
+    }
+}
+
+/// Resolve `raw_path` to an absolute file and build the `open-file` payload
+/// carrying its containing workspace and workspace-relative path. A relative
+/// `raw_path` is joined against `cwd` — the *invoking* shell's working
+/// directory — rather than this process's own, since for a second-instance
+/// launch those can differ.
+fn resolve_open_file_payload(raw_path: &str, line: Option<u32>, cwd: &Path) -> Option<OpenFilePayload> {
+    let candidate = PathBuf::from(raw_path);
+    let candidate = if candidate.is_absolute() {
+        candidate
+    } else {
+        cwd.join(candidate)
+    };
+    let abs = candidate.canonicalize().ok()?;
+    let workspace = find_containing_workspace(&abs);
+    let rel_path = abs
+        .strip_prefix(&workspace)
+        .map(|r| r.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| abs.to_string_lossy().replace('\\', "/"));
+
+    Some(OpenFilePayload {
+        workspace: workspace.to_string_lossy().to_string(),
+        rel_path,
+        line,
+    })
+}
+
+/// Stash `payload` for `label` so the window's frontend can claim it once
+/// mounted via `take_pending_open_file`, instead of emitting `open-file`
+/// before anything is listening (Tauri doesn't buffer/replay custom events).
+fn stash_open_file(app: &AppHandle, label: &str, payload: OpenFilePayload) {
+    if let Ok(mut pending) = app.state::<AppState>().pending_open_files.lock() {
+        pending.insert(label.to_string(), payload);
+    }
+}
+
+/// Open `payload` in a brand-new webview window instead of the existing one,
+/// mirroring how the main window is built but with a fresh, unique label.
+/// The window is freshly created, so its frontend isn't listening yet either
+/// — stash rather than emit, same as the initial-launch path.
+fn open_in_new_window(app: &AppHandle, payload: &OpenFilePayload) {
+    let id = app.state::<AppState>().next_window_id.fetch_add(1, Ordering::Relaxed);
+    let label = format!("note-{id}");
+
+    stash_open_file(app, &label, payload.clone());
+
+    let _ = WebviewWindowBuilder::new(app, label, WebviewUrl::App("index.html".into()))
+        .title("Strata")
+        .build();
+}
+
+/// Handle argv forwarded from a second instance to this already-running one.
+/// The main window's frontend is already mounted and listening, so the
+/// default case can emit `open-file` directly; `--new-window` opens (and
+/// stashes for) a fresh window instead. Returns whether `--new-window` was
+/// requested, so the caller can decide whether to also refocus the main window.
+/// `cwd` is the *second instance's* working directory, as handed to us by
+/// `tauri_plugin_single_instance` — a relative path arg must resolve against
+/// that, not against this (the already-running primary) process's own cwd.
+fn handle_launch_args(app: &AppHandle, argv: &[String], cwd: &Path) -> bool {
+    let (path, line, new_window) = parse_launch_args(argv);
+
+    if let Some(path) = path {
+        if let Some(payload) = resolve_open_file_payload(&path, line, cwd) {
+            if new_window {
+                open_in_new_window(app, &payload);
+            } else {
+                let _ = app.emit("open-file", &payload);
+            }
+        }
+    }
+
+    new_window
+}
+
+/// Stash a cold launch's `path[:line]` argument (if any) as the `main`
+/// window's pending open-file target. Called from `setup`, before the main
+/// window's frontend has had a chance to attach an `open-file` listener.
+/// This is the initial process's own launch, so its own `current_dir` is the
+/// right thing to resolve a relative path against.
+fn stash_initial_open_file(app: &AppHandle, argv: &[String]) {
+    let (path, line, _new_window) = parse_launch_args(argv);
+    let Some(path) = path else {
+        return;
+    };
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let Some(payload) = resolve_open_file_payload(&path, line, &cwd) else {
+        return;
+    };
+    stash_open_file(app, "main", payload);
+}
+
+/// Return (and clear) the pending open-file target for the calling window,
+/// if any. The frontend calls this once it has mounted and is ready to
+/// receive it, since a cold launch can't rely on emitting `open-file` before
+/// any listener exists.
+#[tauri::command]
+fn take_pending_open_file(window: tauri::Window, state: tauri::State<'_, AppState>) -> Option<OpenFilePayload> {
+    state.pending_open_files.lock().ok()?.remove(window.label())
+}
+
 /// Create a directory if it doesn't already exist.
 #[tauri::command]
-fn ensure_dir(path: String) -> Result<(), String> {
-    fs::create_dir_all(&path).map_err(|e| e.to_string())
+fn ensure_dir(path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let resolved = resolve_in_workspace(&path, &state)?;
+    fs::create_dir_all(&resolved).map_err(|e| e.to_string())
 }
 
 // ── File watcher commands ──
@@ -140,18 +527,140 @@ fn mark_file_write(path: String, state: tauri::State<'_, AppState>) -> Result<()
     Ok(())
 }
 
+/// Spawn the background thread that flushes coalesced events once their
+/// debounce window has passed, emitting one `FsEvent` per path.
+fn spawn_debounce_flusher(
+    handle: AppHandle,
+    watch_dir: PathBuf,
+    pending: Arc<PendingBuffer>,
+    stop: Arc<AtomicBool>,
+    search_index: Arc<Mutex<SearchIndex>>,
+    trash_guard: Arc<Mutex<HashSet<PathBuf>>>,
+    debounce_window: Duration,
+) {
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(DEBOUNCE_TICK);
+
+            let mut ready = Vec::new();
+            {
+                let mut buf = match pending.lock() {
+                    Ok(b) => b,
+                    Err(_) => continue,
+                };
+                let now = Instant::now();
+                buf.retain(|path, (kind, seen_at)| {
+                    if now.duration_since(*seen_at) >= debounce_window {
+                        ready.push((path.clone(), *kind));
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
+
+            for (path, kind) in ready {
+                let rel_path = match path.strip_prefix(&watch_dir) {
+                    Ok(r) => r.to_string_lossy().replace('\\', "/"),
+                    Err(_) => continue,
+                };
+                let rel = PathBuf::from(&rel_path);
+                let payload = FsEvent { rel_path };
+
+                match kind {
+                    PendingKind::Created => {
+                        if is_strata_file(&path) {
+                            if let Ok(mut index) = search_index.lock() {
+                                index.index_file(&watch_dir, &rel);
+                            }
+                            let _ = handle.emit("fs:created", &payload);
+                        }
+                    }
+                    PendingKind::Modified => {
+                        if is_strata_file(&path) {
+                            if let Ok(mut index) = search_index.lock() {
+                                index.index_file(&watch_dir, &rel);
+                            }
+                            let _ = handle.emit("fs:modified", &payload);
+                        } else if let Ok(mut index) = search_index.lock() {
+                            // The edit dropped the strata frontmatter (or changed
+                            // `doc-type` away from it) — evict the stale entry
+                            // instead of leaving old tokens/offsets in the index.
+                            index.remove_file(&rel);
+                        }
+                    }
+                    PendingKind::Removed => {
+                        if let Ok(mut index) = search_index.lock() {
+                            index.remove_file(&rel);
+                        }
+                        // Already reported via `fs:trashed` by `delete_file` — don't double-report
+                        let already_trashed = trash_guard.lock().map(|mut g| g.remove(&path)).unwrap_or(false);
+                        if !already_trashed {
+                            let _ = handle.emit("fs:deleted", &payload);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
 #[tauri::command]
-fn start_watching(workspace: String, app_handle: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    // Stop any existing watcher first
+fn start_watching(
+    workspace: String,
+    debounce_ms: Option<u64>,
+    app_handle: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let debounce_window = Duration::from_millis(debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_WINDOW_MS));
+
+    // Stop any existing watcher and debounce thread first
     {
         let mut w = state.watcher.lock().map_err(|e| e.to_string())?;
         *w = None;
     }
+    {
+        let mut stop = state.debounce_stop.lock().map_err(|e| e.to_string())?;
+        if let Some(prev) = stop.take() {
+            prev.store(true, Ordering::Relaxed);
+        }
+    }
+
+    let watch_path = set_workspace_root(&workspace, &state)?;
+    let matcher = build_ignore_matcher(&watch_path);
+
+    let mut files = Vec::new();
+    walk_dir(&watch_path, &watch_path, &matcher, &mut files)?;
+    {
+        let mut index = state.search_index.lock().map_err(|e| e.to_string())?;
+        for rel in &files {
+            index.index_file(&watch_path, Path::new(rel));
+        }
+    }
+
+    {
+        let mut guard = state.ignore.lock().map_err(|e| e.to_string())?;
+        *guard = matcher;
+    }
 
-    let watch_path = PathBuf::from(&workspace);
     let handle = app_handle.clone();
     let write_guard = Arc::clone(&app_handle.state::<AppState>().inner().write_guard);
+    let ignore = Arc::clone(&app_handle.state::<AppState>().inner().ignore);
     let watch_dir = watch_path.clone();
+    let pending: Arc<PendingBuffer> = Arc::new(Mutex::new(HashMap::new()));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    let search_index = Arc::clone(&app_handle.state::<AppState>().inner().search_index);
+    let trash_guard = Arc::clone(&app_handle.state::<AppState>().inner().trash_guard);
+    spawn_debounce_flusher(
+        handle,
+        watch_dir.clone(),
+        Arc::clone(&pending),
+        Arc::clone(&stop_flag),
+        search_index,
+        trash_guard,
+        debounce_window,
+    );
 
     let mut watcher = notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
         let event = match res {
@@ -159,6 +668,13 @@ fn start_watching(workspace: String, app_handle: AppHandle, state: tauri::State<
             Err(_) => return,
         };
 
+        let kind = match event.kind {
+            EventKind::Create(_) => PendingKind::Created,
+            EventKind::Modify(_) => PendingKind::Modified,
+            EventKind::Remove(_) => PendingKind::Removed,
+            _ => return,
+        };
+
         for path in &event.paths {
             let name = match path.file_name() {
                 Some(n) => n.to_string_lossy().to_string(),
@@ -183,6 +699,21 @@ fn start_watching(workspace: String, app_handle: AppHandle, state: tauri::State<
                 continue; // outside watch dir
             }
 
+            // Skip paths matched by .gitignore / .strataignore
+            {
+                let guard = match ignore.lock() {
+                    Ok(g) => g,
+                    Err(_) => continue,
+                };
+                // Check parent directories too, so files under an ignored
+                // directory are skipped just as `walk_dir` never descends into it.
+                let ignored = path.starts_with(guard.path())
+                    && guard.matched_path_or_any_parents(path, path.is_dir()).is_ignore();
+                if ignored {
+                    continue;
+                }
+            }
+
             // Check write guard — if we wrote this file, skip the event
             {
                 let mut guard = match write_guard.lock() {
@@ -194,30 +725,18 @@ fn start_watching(workspace: String, app_handle: AppHandle, state: tauri::State<
                 }
             }
 
-            // Compute relative path from workspace root
-            let rel_path = match path.strip_prefix(&watch_dir) {
-                Ok(r) => r.to_string_lossy().replace('\\', "/"),
+            // Buffer the event; the debounce thread coalesces and flushes it
+            let mut buf = match pending.lock() {
+                Ok(b) => b,
                 Err(_) => continue,
             };
-
-            let payload = FsEvent { rel_path };
-
-            match event.kind {
-                EventKind::Create(_) => {
-                    if is_strata_file(path) {
-                        let _ = handle.emit("fs:created", &payload);
-                    }
-                }
-                EventKind::Modify(_) => {
-                    if is_strata_file(path) {
-                        let _ = handle.emit("fs:modified", &payload);
-                    }
+            let effective = match kind {
+                PendingKind::Modified if matches!(buf.get(path), Some((PendingKind::Created, _))) => {
+                    PendingKind::Created
                 }
-                EventKind::Remove(_) => {
-                    let _ = handle.emit("fs:deleted", &payload);
-                }
-                _ => {}
-            }
+                other => other,
+            };
+            buf.insert(path.clone(), (effective, Instant::now()));
         }
     }).map_err(|e| e.to_string())?;
 
@@ -227,6 +746,8 @@ fn start_watching(workspace: String, app_handle: AppHandle, state: tauri::State<
 
     let mut w = state.watcher.lock().map_err(|e| e.to_string())?;
     *w = Some(watcher);
+    let mut stop = state.debounce_stop.lock().map_err(|e| e.to_string())?;
+    *stop = Some(stop_flag);
 
     Ok(())
 }
@@ -235,9 +756,21 @@ fn start_watching(workspace: String, app_handle: AppHandle, state: tauri::State<
 fn stop_watching(state: tauri::State<'_, AppState>) -> Result<(), String> {
     let mut w = state.watcher.lock().map_err(|e| e.to_string())?;
     *w = None;
+    let mut stop = state.debounce_stop.lock().map_err(|e| e.to_string())?;
+    if let Some(prev) = stop.take() {
+        prev.store(true, Ordering::Relaxed);
+    }
     Ok(())
 }
 
+// ── Search ──
+
+#[tauri::command]
+fn search_workspace(query: String, limit: usize, state: tauri::State<'_, AppState>) -> Result<Vec<SearchHit>, String> {
+    let index = state.search_index.lock().map_err(|e| e.to_string())?;
+    Ok(index.search(&query, limit))
+}
+
 // ── Menu builder ──
 
 fn build_menu(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
@@ -322,10 +855,26 @@ fn build_menu(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            let new_window = handle_launch_args(app, &argv, Path::new(&cwd));
+            if !new_window {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.unminimize();
+                    let _ = window.set_focus();
+                }
+            }
+        }))
         .plugin(tauri_plugin_dialog::init())
         .manage(AppState {
             watcher: Mutex::new(None),
             write_guard: Arc::new(Mutex::new(HashSet::new())),
+            workspace_root: Mutex::new(None),
+            ignore: Arc::new(Mutex::new(Gitignore::empty())),
+            debounce_stop: Mutex::new(None),
+            search_index: Arc::new(Mutex::new(SearchIndex::new())),
+            trash_guard: Arc::new(Mutex::new(HashSet::new())),
+            next_window_id: std::sync::atomic::AtomicU32::new(0),
+            pending_open_files: Mutex::new(HashMap::new()),
         })
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -338,6 +887,11 @@ pub fn run() {
 
             build_menu(app)?;
 
+            // Stash a path[:line] argument from the primary instance's own launch;
+            // the frontend claims it via `take_pending_open_file` once mounted.
+            let argv: Vec<String> = std::env::args().collect();
+            stash_initial_open_file(&app.handle(), &argv);
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -352,7 +906,107 @@ pub fn run() {
             mark_file_write,
             start_watching,
             stop_watching,
+            search_workspace,
+            restore_file,
+            take_pending_open_file,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedot_collapses_dot_and_dotdot() {
+        let collapsed = dedot(Path::new("/ws/a/./b/../c"));
+        assert_eq!(collapsed, PathBuf::from("/ws/a/c"));
+    }
+
+    #[test]
+    fn dedot_cannot_pop_past_root() {
+        // A leading `..` has nothing to pop, so it's dropped rather than
+        // climbing above the first component — `resolve_against_root`'s
+        // `starts_with` check is what actually rejects the escape attempt.
+        let collapsed = dedot(Path::new("../../etc/passwd"));
+        assert_eq!(collapsed, PathBuf::from("etc/passwd"));
+    }
+
+    #[test]
+    fn resolve_against_root_allows_paths_inside_workspace() {
+        let root = Path::new("/ws");
+        let resolved = resolve_against_root(Some(root), "notes/todo.md").unwrap();
+        assert_eq!(resolved, PathBuf::from("/ws/notes/todo.md"));
+    }
+
+    #[test]
+    fn resolve_against_root_rejects_traversal_outside_workspace() {
+        let root = Path::new("/ws");
+        let err = resolve_against_root(Some(root), "../../etc/passwd").unwrap_err();
+        assert_eq!(err, "path escapes the workspace");
+    }
+
+    #[test]
+    fn resolve_against_root_rejects_absolute_escape() {
+        let root = Path::new("/ws");
+        let err = resolve_against_root(Some(root), "/etc/passwd").unwrap_err();
+        assert_eq!(err, "path escapes the workspace");
+    }
+
+    #[test]
+    fn resolve_against_root_requires_open_workspace() {
+        let err = resolve_against_root(None, "notes/todo.md").unwrap_err();
+        assert_eq!(err, "no workspace is open");
+    }
+
+    /// A directory under the OS temp dir that's removed on drop, so symlink
+    /// tests clean up after themselves even on panic.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("strata-test-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn resolve_against_root_rejects_symlink_escape() {
+        let workspace = TempDir::new("symlink-workspace");
+        let outside = TempDir::new("symlink-outside");
+        let secret = outside.0.join("secret.txt");
+        fs::write(&secret, "hunter2").unwrap();
+
+        let link = workspace.0.join("escape.md");
+        std::os::unix::fs::symlink(&secret, &link).unwrap();
+
+        let root = workspace.0.canonicalize().unwrap();
+        let err = resolve_against_root(Some(&root), "escape.md").unwrap_err();
+        assert_eq!(err, "path escapes the workspace");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn resolve_against_root_allows_symlink_within_workspace() {
+        let workspace = TempDir::new("symlink-internal");
+        let target = workspace.0.join("real.md");
+        fs::write(&target, "hello").unwrap();
+
+        let link = workspace.0.join("alias.md");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let root = workspace.0.canonicalize().unwrap();
+        let resolved = resolve_against_root(Some(&root), "alias.md").unwrap();
+        assert_eq!(resolved, link);
+    }
+}
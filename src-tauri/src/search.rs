@@ -0,0 +1,234 @@
+//! In-memory inverted index over strata documents, kept live by the watcher.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// One ranked match returned by [`SearchIndex::search`].
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub rel_path: String,
+    pub score: f64,
+    /// Token offsets into the (frontmatter-stripped) body where a query term matched.
+    pub snippets: Vec<usize>,
+}
+
+/// Per-document length used for BM25 normalization; the token positions
+/// themselves live in `SearchIndex::postings`.
+#[derive(Default)]
+struct DocEntry {
+    token_count: usize,
+}
+
+/// Inverted index keyed by workspace-relative path, ranked with BM25.
+#[derive(Default)]
+pub struct SearchIndex {
+    postings: HashMap<String, HashMap<PathBuf, Vec<usize>>>,
+    docs: HashMap<PathBuf, DocEntry>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop every indexed document, e.g. when the open workspace changes.
+    pub fn clear(&mut self) {
+        self.postings.clear();
+        self.docs.clear();
+    }
+
+    /// Re-tokenize `rel_path` (resolved against `root`) and replace its entry.
+    pub fn index_file(&mut self, root: &Path, rel_path: &Path) {
+        self.remove_file(rel_path);
+
+        let content = match fs::read_to_string(root.join(rel_path)) {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+        let body = strip_frontmatter(&content);
+        let tokens = tokenize(body);
+
+        self.docs.insert(rel_path.to_path_buf(), DocEntry { token_count: tokens.len() });
+        for (offset, token) in tokens.into_iter().enumerate() {
+            self.postings
+                .entry(token)
+                .or_default()
+                .entry(rel_path.to_path_buf())
+                .or_default()
+                .push(offset);
+        }
+    }
+
+    /// Evict a document from the index, e.g. on `fs:deleted`.
+    pub fn remove_file(&mut self, rel_path: &Path) {
+        if self.docs.remove(rel_path).is_none() {
+            return;
+        }
+        for docs in self.postings.values_mut() {
+            docs.remove(rel_path);
+        }
+    }
+
+    /// Rank documents against `query` using BM25 over the indexed tokens.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() || self.docs.is_empty() {
+            return Vec::new();
+        }
+
+        let doc_count = self.docs.len() as f64;
+        let avg_len = self.docs.values().map(|d| d.token_count).sum::<usize>() as f64 / doc_count;
+
+        let mut scores: HashMap<PathBuf, f64> = HashMap::new();
+        let mut matched_offsets: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+
+        for term in &terms {
+            let Some(docs_for_term) = self.postings.get(term) else {
+                continue;
+            };
+            let n_q = docs_for_term.len() as f64;
+            let idf = ((doc_count - n_q + 0.5) / (n_q + 0.5) + 1.0).ln();
+
+            for (path, positions) in docs_for_term {
+                let doc_len = self.docs.get(path).map_or(avg_len, |d| d.token_count as f64);
+                let tf = positions.len() as f64;
+                let denom = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * doc_len / avg_len.max(1.0));
+                *scores.entry(path.clone()).or_insert(0.0) += idf * (tf * (BM25_K1 + 1.0)) / denom;
+                matched_offsets.entry(path.clone()).or_default().extend(positions.iter().copied());
+            }
+        }
+
+        let mut ranked: Vec<(PathBuf, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(limit);
+
+        ranked
+            .into_iter()
+            .map(|(path, score)| {
+                let mut snippets = matched_offsets.remove(&path).unwrap_or_default();
+                snippets.sort_unstable();
+                snippets.dedup();
+                snippets.truncate(5);
+                SearchHit {
+                    rel_path: path.to_string_lossy().replace('\\', "/"),
+                    score,
+                    snippets,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Strip a leading `---`-delimited YAML frontmatter block, using the same
+/// parsing `is_strata_file` in `lib.rs` sniffs `doc-type` out of.
+fn strip_frontmatter(content: &str) -> &str {
+    match crate::frontmatter::span(content) {
+        Some((_, end)) => &content[end + 4..],
+        None => content,
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_frontmatter_removes_leading_block() {
+        let content = "---\ndoc-type: strata\ntitle: Test\n---\nBody text here.";
+        assert_eq!(strip_frontmatter(content), "\nBody text here.");
+    }
+
+    #[test]
+    fn strip_frontmatter_no_block_returns_unchanged() {
+        let content = "Just a plain document.\nNo frontmatter.";
+        assert_eq!(strip_frontmatter(content), content);
+    }
+
+    #[test]
+    fn strip_frontmatter_unterminated_block_returns_unchanged() {
+        let content = "---\ndoc-type: strata\ntitle: Test\nBody never closes.";
+        assert_eq!(strip_frontmatter(content), content);
+    }
+
+    #[test]
+    fn tokenize_lowercases_and_splits_on_punctuation() {
+        assert_eq!(tokenize("Hello, World!"), vec!["hello".to_string(), "world".to_string()]);
+    }
+
+    /// A directory under the OS temp dir that's removed on drop, so these
+    /// filesystem-backed tests clean up after themselves even on panic.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("strata-search-test-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn index_file_then_remove_file_round_trip() {
+        let dir = TempDir::new("roundtrip");
+        fs::write(dir.0.join("doc.md"), "---\ndoc-type: strata\n---\nhello world\n").unwrap();
+
+        let mut index = SearchIndex::new();
+        index.index_file(&dir.0, Path::new("doc.md"));
+        assert_eq!(index.search("hello", 10).len(), 1);
+
+        index.remove_file(Path::new("doc.md"));
+        assert!(index.search("hello", 10).is_empty());
+        assert!(index.docs.is_empty());
+        assert!(index.postings.values().all(|docs| docs.is_empty()));
+    }
+
+    #[test]
+    fn bm25_ranks_shorter_denser_document_first() {
+        let dir = TempDir::new("bm25-ranking");
+        // Both docs mention "apple" once; `b.md` pads with enough unrelated
+        // tokens to dilute its relevance, so BM25's length normalization
+        // should rank the shorter, denser `a.md` above it.
+        fs::write(dir.0.join("a.md"), "apple banana").unwrap();
+        fs::write(
+            dir.0.join("b.md"),
+            "apple banana banana banana banana banana banana banana banana banana banana",
+        )
+        .unwrap();
+
+        let mut index = SearchIndex::new();
+        index.index_file(&dir.0, Path::new("a.md"));
+        index.index_file(&dir.0, Path::new("b.md"));
+
+        let hits = index.search("apple", 10);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].rel_path, "a.md");
+        assert!(hits[0].score > hits[1].score);
+    }
+
+    #[test]
+    fn search_returns_nothing_for_empty_index() {
+        let index = SearchIndex::new();
+        assert!(index.search("anything", 10).is_empty());
+    }
+}